@@ -0,0 +1,28 @@
+use clap::Parser;
+use prime_utils::factor;
+
+#[derive(Parser)]
+#[command(
+    name = "factor",
+    about = "Factors the given integer into its prime factors (unsigned 64 bit)",
+    version
+)]
+
+struct Cli {
+    /// Number to factor
+    number: u64,
+}
+
+
+fn main() {
+    let cli = Cli::parse();
+
+    let factors = factor(cli.number);
+    if factors.is_empty() {
+        println!("{} has no prime factorization", cli.number);
+        return;
+    }
+
+    let rendered: Vec<String> = factors.iter().map(u64::to_string).collect();
+    println!("{}", rendered.join(" * "));
+}